@@ -17,30 +17,28 @@
 
 use super::*;
 
-use indexmap::IndexMap;
-use leo_ast::Stub;
-use leo_compiler::{Compiler, CompilerOptions};
-use leo_errors::{CliError, UtilError};
-use leo_retriever::{Manifest, NetworkName, Retriever};
-use leo_linter::Linter;
-use leo_span::Symbol;
-use leo_package::{build::BuildDirectory, outputs::OutputsDirectory, source::SourceDirectory};
-use std::fs;
-use snarkvm::prelude::CanaryV0;
-use std::{
-    io::Write,
-    path::{Path, PathBuf},
-    option::Option
-};
-use snarkvm::{
-    package::Package,
-    prelude::{MainnetV0, Network, ProgramID, TestnetV0},
-};
-
-
+use leo_errors::UtilError;
+use leo_retriever::{Manifest, NetworkName};
+use leo_linter::{FormatMode, Linter};
+use std::path::PathBuf;
+use snarkvm::prelude::{CanaryV0, MainnetV0, Network, ProgramID, TestnetV0};
 
 #[derive(Parser, Debug)]
-pub struct Format {}
+pub struct Format {
+    #[clap(long, help = "Don't write any files; report which files would be reformatted and fail if any would.")]
+    pub check: bool,
+    #[clap(long, help = "Implies --check; also print a unified diff for every file that would be reformatted.")]
+    pub diff: bool,
+    #[clap(long, help = "Also format local path dependencies of this package.")]
+    pub include_deps: bool,
+    #[clap(long, help = "Network to format the package for.", default_value = "testnet")]
+    pub network: NetworkName,
+    #[clap(
+        long,
+        help = "Format purely from the local source directory; never construct a Retriever or contact a network endpoint."
+    )]
+    pub offline: bool,
+}
 
 impl Command for Format {
     type Input = ();
@@ -61,11 +59,50 @@ impl Command for Format {
 fn handle_format(command: &Format, context: Context) -> Result<<Format as Command>::Output> {
     let package_path = context.dir()?;
     let home_path = context.home()?;
-    let endpoint = String::from("https://api.explorer.aleo.org/v1");
+    let endpoint = explorer_endpoint(command.network);
     let manifest = Manifest::read_from_dir(&package_path)?;
-    let program_id = ProgramID::<TestnetV0>::from_str(manifest.program())?;
-    let linter = Linter::<TestnetV0>::new(program_id, endpoint, package_path, home_path)
-    .map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
+    let mode = if command.diff {
+        FormatMode::Diff
+    } else if command.check {
+        FormatMode::Check
+    } else {
+        FormatMode::Write
+    };
+
+    match command.network {
+        NetworkName::MainnetV0 => {
+            format_for_network::<MainnetV0>(manifest, endpoint, package_path, home_path, mode, command)
+        }
+        NetworkName::TestnetV0 => {
+            format_for_network::<TestnetV0>(manifest, endpoint, package_path, home_path, mode, command)
+        }
+        NetworkName::CanaryV0 => {
+            format_for_network::<CanaryV0>(manifest, endpoint, package_path, home_path, mode, command)
+        }
+    }
+}
+
+/// The explorer endpoint to retrieve dependencies from for a given network. Each network has
+/// its own explorer, so a `--network canary` run must never contact the mainnet explorer.
+fn explorer_endpoint(network: NetworkName) -> String {
+    match network {
+        NetworkName::MainnetV0 => String::from("https://api.explorer.aleo.org/v1"),
+        NetworkName::TestnetV0 => String::from("https://api.testnet.explorer.aleo.org/v1"),
+        NetworkName::CanaryV0 => String::from("https://api.canary.explorer.aleo.org/v1"),
+    }
+}
+
+fn format_for_network<N: Network>(
+    manifest: Manifest,
+    endpoint: String,
+    package_path: PathBuf,
+    home_path: PathBuf,
+    mode: FormatMode,
+    command: &Format,
+) -> Result<<Format as Command>::Output> {
+    let program_id = ProgramID::<N>::from_str(manifest.program())?;
+    let linter = Linter::<N>::new(program_id, endpoint, package_path, home_path, mode, command.include_deps, command.offline)
+        .map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
     linter.lint()?;
     Ok(())
 }
@@ -0,0 +1,237 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Collects source comments so the pretty-printer can re-attach them to the AST nodes
+//! they were written next to, instead of dropping them on the floor during formatting.
+
+use leo_span::span::Span;
+use std::cell::RefCell;
+
+/// A single `//` or `/* */` comment, along with the line it starts and ends on.
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub span: Span,
+    pub text: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// All comments found in a source file. Each comment is attached to exactly one AST node:
+/// once `leading` or `trailing` hands a comment out, it's marked claimed and will never be
+/// returned again, so the same `// note` can't be printed both as a statement's trailing
+/// comment and as the next statement's leading comment.
+#[derive(Clone, Debug, Default)]
+pub struct CommentMap {
+    comments: Vec<Comment>,
+    claimed: RefCell<Vec<bool>>,
+}
+
+impl CommentMap {
+    /// Scans `source` for `//` line comments and `/* */` block comments, skipping over
+    /// anything that appears inside a string or character literal so that braces,
+    /// semicolons, and comment markers inside literals are never mistaken for comments.
+    pub fn collect(source: &str) -> Self {
+        let bytes = source.as_bytes();
+        let mut comments = Vec::new();
+        let mut i = 0;
+        let mut line = 1usize;
+        let mut in_string = false;
+        let mut in_char = false;
+
+        while i < bytes.len() {
+            let c = bytes[i] as char;
+
+            if in_string || in_char {
+                if c == '\\' {
+                    i += 2;
+                    continue;
+                }
+                if (in_string && c == '"') || (in_char && c == '\'') {
+                    in_string = false;
+                    in_char = false;
+                }
+                if c == '\n' {
+                    line += 1;
+                }
+                i += 1;
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    in_string = true;
+                    i += 1;
+                }
+                '\'' => {
+                    in_char = true;
+                    i += 1;
+                }
+                '/' if bytes.get(i + 1) == Some(&b'/') => {
+                    let start = i;
+                    let start_line = line;
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                    comments.push(Comment {
+                        span: Span::new(start as u32, i as u32),
+                        text: source[start..i].to_string(),
+                        start_line,
+                        end_line: start_line,
+                    });
+                }
+                '/' if bytes.get(i + 1) == Some(&b'*') => {
+                    let start = i;
+                    let start_line = line;
+                    i += 2;
+                    while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                        if bytes[i] == b'\n' {
+                            line += 1;
+                        }
+                        i += 1;
+                    }
+                    i = (i + 2).min(bytes.len());
+                    comments.push(Comment {
+                        span: Span::new(start as u32, i as u32),
+                        text: source[start..i].to_string(),
+                        start_line,
+                        end_line: line,
+                    });
+                }
+                '\n' => {
+                    line += 1;
+                    i += 1;
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+
+        let claimed = RefCell::new(vec![false; comments.len()]);
+        Self { comments, claimed }
+    }
+
+    /// Returns the unclaimed comments that sit immediately above `line`, each ending on the line
+    /// before the next, in source order, and marks them claimed. A comment that shares its line
+    /// with code is returned too unless `trailing` already claimed it. Stops as soon as it would otherwise
+    /// re-claim a comment already handed out by `trailing` — that comment shared a line with
+    /// code, so it ends a run of standalone comment lines rather than extending one.
+    pub fn leading(&self, line: usize) -> Vec<&Comment> {
+        let mut out: Vec<&Comment> = Vec::new();
+        let mut claimed = self.claimed.borrow_mut();
+        let mut expected = line;
+        for i in (0..self.comments.len()).rev() {
+            let comment = &self.comments[i];
+            if comment.end_line + 1 == expected {
+                if claimed[i] {
+                    break;
+                }
+                claimed[i] = true;
+                out.push(comment);
+                expected = comment.start_line;
+            } else if comment.end_line < line {
+                break;
+            }
+        }
+        out.reverse();
+        out
+    }
+
+    /// Returns the trailing comment that shares `line` with the node that ends there, if any,
+    /// and marks it claimed so `leading` won't hand it out again for the next node.
+    pub fn trailing(&self, line: usize) -> Option<&Comment> {
+        let mut claimed = self.claimed.borrow_mut();
+        for (i, comment) in self.comments.iter().enumerate() {
+            if !claimed[i] && comment.start_line == line {
+                claimed[i] = true;
+                return Some(comment);
+            }
+        }
+        None
+    }
+
+    /// Reports whether [`CommentMap::leading`] would return anything for `line`, without
+    /// claiming any comments. Used to decide whether a node can be safely inlined onto another
+    /// line without a standalone comment swallowing what follows it.
+    pub fn has_leading(&self, line: usize) -> bool {
+        let claimed = self.claimed.borrow();
+        let mut expected = line;
+        for i in (0..self.comments.len()).rev() {
+            let comment = &self.comments[i];
+            if comment.end_line + 1 == expected {
+                if claimed[i] {
+                    break;
+                }
+                return true;
+            } else if comment.end_line < line {
+                break;
+            }
+        }
+        false
+    }
+
+    /// Reports whether [`CommentMap::trailing`] would return something for `line`, without
+    /// claiming it. Used for the same reason as [`CommentMap::has_leading`]: inlining a node
+    /// that carries a trailing `//` comment onto the same line as whatever follows it would
+    /// comment that out too.
+    pub fn has_trailing(&self, line: usize) -> bool {
+        let claimed = self.claimed.borrow();
+        self.comments.iter().enumerate().any(|(i, comment)| !claimed[i] && comment.start_line == line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_comment_is_not_also_returned_as_leading() {
+        let source = "let x: u8 = 1u8; // note\nlet y: u8 = 2u8;\n";
+        let comments = CommentMap::collect(source);
+
+        let trailing = comments.trailing(1).expect("line 1 has a trailing comment");
+        assert_eq!(trailing.text, "// note");
+        assert!(comments.leading(2).is_empty(), "the trailing comment must not be reused as a leading comment");
+    }
+
+    #[test]
+    fn leading_comment_chain_is_attached_once() {
+        let source = "// first\n// second\nlet x: u8 = 1u8;\n";
+        let comments = CommentMap::collect(source);
+
+        let leading = comments.leading(3);
+        assert_eq!(leading.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), vec!["// first", "// second"]);
+        assert!(comments.leading(3).is_empty(), "a claimed comment must not be returned a second time");
+    }
+
+    #[test]
+    fn block_comment_containing_a_brace_is_collected_as_one_comment() {
+        let source = "/* brace { inside a comment */\nlet x: u8 = 1u8;\n";
+        let comments = CommentMap::collect(source);
+        assert_eq!(comments.leading(2).len(), 1);
+    }
+
+    #[test]
+    fn has_leading_does_not_claim_the_comment() {
+        let source = "// note\nlet x: u8 = 1u8;\n";
+        let comments = CommentMap::collect(source);
+
+        assert!(comments.has_leading(2));
+        assert!(comments.has_leading(2), "has_leading must not claim the comment it finds");
+        let leading = comments.leading(2);
+        assert_eq!(leading.len(), 1, "the comment must still be available to leading() afterwards");
+    }
+}
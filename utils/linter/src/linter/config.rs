@@ -0,0 +1,98 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Style configuration for the formatter, loaded from an optional `leo.fmt.toml` at the
+//! package root. Teams that don't care get the defaults below; teams that do can check a
+//! `leo.fmt.toml` into version control to standardize a house style.
+
+use leo_errors::CliError;
+use serde::Deserialize;
+use snarkvm::prelude::Error;
+use std::{fs, path::Path};
+
+/// The name of the optional per-package formatter configuration file.
+pub const FORMATTER_CONFIG_FILE_NAME: &str = "leo.fmt.toml";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FormatterConfig {
+    /// Number of spaces per indentation level; ignored by `indent_unit` when `use_tabs` is set.
+    pub indent_width: usize,
+    /// Indent with one tab per level instead of `indent_width` spaces.
+    pub use_tabs: bool,
+    /// Target maximum line width. Only honored for function parameter lists of two or more
+    /// parameters, which wrap one-per-line once a signature would exceed it; other long
+    /// expressions (call arguments, const/let initializers, ...) are not wrapped.
+    pub max_width: usize,
+    /// Collapse a block containing a single simple statement onto one line, e.g. `{ return x; }`.
+    pub collapse_simple_blocks: bool,
+    /// Number of blank lines to leave between top-level declarations. Consecutive `const`
+    /// declarations are printed with no blank lines between them.
+    pub blank_lines_between_items: usize,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            use_tabs: false,
+            max_width: 100,
+            collapse_simple_blocks: false,
+            blank_lines_between_items: 1,
+        }
+    }
+}
+
+impl FormatterConfig {
+    /// Discovers and parses `leo.fmt.toml` in `package_root`, falling back to
+    /// [`FormatterConfig::default`] when no such file exists.
+    pub fn discover(package_root: &Path) -> Result<Self, Error> {
+        let config_path = package_root.join(FORMATTER_CONFIG_FILE_NAME);
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&config_path).map_err(CliError::build_error)?;
+        toml::from_str(&contents).map_err(CliError::build_error)
+    }
+
+    /// A single level of indentation, as the literal text to prepend to a line.
+    pub fn indent_unit(&self) -> String {
+        if self.use_tabs { "\t".to_string() } else { " ".repeat(self.indent_width) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_falls_back_to_defaults_when_no_config_file_exists() {
+        let package_root = std::env::temp_dir().join("leo-formatter-config-test-missing");
+        let config = FormatterConfig::discover(&package_root).expect("missing config file is not an error");
+        assert_eq!(config.indent_width, FormatterConfig::default().indent_width);
+        assert_eq!(config.max_width, FormatterConfig::default().max_width);
+    }
+
+    #[test]
+    fn indent_unit_honors_use_tabs() {
+        let spaces = FormatterConfig { use_tabs: false, indent_width: 2, ..FormatterConfig::default() };
+        assert_eq!(spaces.indent_unit(), "  ");
+
+        let tabs = FormatterConfig { use_tabs: true, ..FormatterConfig::default() };
+        assert_eq!(tabs.indent_unit(), "\t");
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A small line-based unified diff, used by `leo format --diff` to show what a run would
+//! change without writing anything to disk.
+
+use std::fmt;
+
+/// Renders a unified diff between `original` and `formatted`, labelled with `path`.
+pub fn unified_diff(path: &str, original: &str, formatted: &str) -> String {
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = formatted.lines().collect();
+    let ops = lcs_diff(&before, &after);
+
+    let mut out = String::new();
+    out.push_str(&format!("--- {path}\n"));
+    out.push_str(&format!("+++ {path}\n"));
+    // A single hunk covering the whole file, since every line is emitted as context or change.
+    let (old_start, new_start) = (usize::from(!before.is_empty()), usize::from(!after.is_empty()));
+    out.push_str(&format!("@@ -{old_start},{} +{new_start},{} @@\n", before.len(), after.len()));
+    for op in ops {
+        match op {
+            DiffOp::Equal(line) => out.push_str(&format!(" {line}\n")),
+            DiffOp::Removed(line) => out.push_str(&format!("-{line}\n")),
+            DiffOp::Added(line) => out.push_str(&format!("+{line}\n")),
+        }
+    }
+    out
+}
+
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// A minimal LCS-based line diff; not the fastest algorithm, but source files are small and
+/// this keeps the formatter dependency-free.
+fn lcs_diff<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let n = before.len();
+    let m = after.len();
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if before[i] == after[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if before[i] == after[j] {
+            ops.push(DiffOp::Equal(before[i]));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            ops.push(DiffOp::Removed(before[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(after[j]));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push(DiffOp::Removed(before[i]));
+        i += 1;
+    }
+    while j < m {
+        ops.push(DiffOp::Added(after[j]));
+        j += 1;
+    }
+    ops
+}
+
+/// Raised when `leo format --check` finds files that are not canonically formatted.
+#[derive(Debug)]
+pub struct UnformattedFilesError {
+    pub count: usize,
+}
+
+impl fmt::Display for UnformattedFilesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} file(s) would be reformatted", self.count)
+    }
+}
+
+impl std::error::Error for UnformattedFilesError {}
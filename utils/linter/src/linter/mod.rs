@@ -14,200 +14,269 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-use indexmap::IndexMap;
-use leo_ast::Stub;
-use leo_compiler::{Compiler, CompilerOptions};
+mod comments;
+mod config;
+mod diff;
+mod printer;
+
+pub use config::FormatterConfig;
+pub use diff::unified_diff;
+
+use leo_ast::NodeBuilder;
 use leo_errors::{CliError, UtilError};
 use leo_errors::emitter::Handler;
-use leo_retriever::Retriever;
+use leo_retriever::{Manifest, Retriever};
 use leo_span::Symbol;
 
+use comments::CommentMap;
+use diff::UnformattedFilesError;
+use printer::PrettyPrinter;
 
-use leo_package::{build::BuildDirectory, outputs::OutputsDirectory, source::SourceDirectory};
+use leo_package::source::SourceDirectory;
 use std::fs;
-use std::{
-    io::Write,
-    path::{Path, PathBuf},
-};
-
-use snarkvm::{
-    package::Package, 
-    prelude::{Network, ProgramID, Error},
-};
+use std::path::{Path, PathBuf};
+
+use snarkvm::prelude::{Network, ProgramID, Error};
+/// How `Linter::lint` should treat files whose canonical formatting differs from what's on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FormatMode {
+    /// Rewrite every file that isn't already canonically formatted.
+    #[default]
+    Write,
+    /// Write nothing; report which files would change and fail if any would.
+    Check,
+    /// Like `Check`, but also print a unified diff for every file that would change.
+    Diff,
+}
+
 pub struct Linter<N: Network> {
     package_path: PathBuf,
     home_path: PathBuf,
     program_id: ProgramID<N>,
     endpoint: String,
+    mode: FormatMode,
+    include_deps: bool,
+    offline: bool,
+    config: FormatterConfig,
 }
 impl<N: Network> Linter<N> {
-    pub fn new(program_id: ProgramID<N>, endpoint: String, package_path: PathBuf, home_path: PathBuf) -> Result<Self, UtilError> {
+    /// Builds a `Linter`, discovering a `leo.fmt.toml` under `package_path` and falling back to
+    /// [`FormatterConfig::default`] if none is present.
+    pub fn new(
+        program_id: ProgramID<N>,
+        endpoint: String,
+        package_path: PathBuf,
+        home_path: PathBuf,
+        mode: FormatMode,
+        include_deps: bool,
+        offline: bool,
+    ) -> Result<Self, UtilError> {
+        let config = FormatterConfig::discover(&package_path)
+            .map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
         Ok(Self {
             package_path: package_path.clone(),
             endpoint: endpoint.clone(),
             program_id: program_id.clone(),
             home_path: home_path.clone(),
+            mode,
+            include_deps,
+            offline,
+            config,
         })
     }
-    
+
+    /// Formats the local package's own source files and, if `include_deps` was requested,
+    /// its local path dependencies. Formatting never compiles anything and never touches
+    /// `build/` or `outputs/`, so it can run on a fresh checkout with no network access and
+    /// without disturbing the user's existing build artifacts. When `offline` is set, this
+    /// never constructs a `Retriever` or contacts `self.endpoint`, even with `include_deps`.
     pub fn lint(&self) -> Result<(), Error> {
-        let build_directory = self.package_path.join("build");
-        if build_directory.exists() {
-            std::fs::remove_dir_all(&build_directory).map_err(CliError::build_error)?;
-        }
-        Package::create(&build_directory, &self.program_id).map_err(CliError::build_error)?;
         let handler = Handler::default();
-        let main_sym = Symbol::intern(&self.program_id.name().to_string());
-        let mut retriever =  Retriever::<N>::new(
-            main_sym, 
-            &self.package_path, 
-            &self.home_path, 
-            self.endpoint.clone()
-        )    
-        .map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
-        let mut local_dependencies = retriever.retrieve().map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
-        local_dependencies.push(main_sym);
-        let recursive_build = true;
-        for dependency in local_dependencies.into_iter() {
-            let (local_path, stubs) = retriever.prepare_local(dependency).map_err(CliError::build_error)?;
-            let local_outputs_directory = OutputsDirectory::create(&local_path).map_err(CliError::build_error)?;
-            let local_build_directory = BuildDirectory::create(&local_path).map_err(CliError::build_error)?;
-            let local_source_files = SourceDirectory::files(&local_path).map_err(CliError::build_error)?;
-            SourceDirectory::check_files(&local_source_files).map_err(CliError::build_error)?;
-            for file_path in local_source_files.clone() {
-                compile_leo_file(
-                    file_path,
-                    &ProgramID::<N>::try_from(format!("{}.aleo", dependency))
-                        .map_err(|_| UtilError::snarkvm_error_building_program_id(Default::default()))?,
-                    &local_outputs_directory,
-                    &local_build_directory,
-                    &handler,
-                    stubs.clone(),
-                )?;
+        let mut unformatted_files = Vec::new();
+
+        self.format_package(&self.package_path, &handler, &mut unformatted_files)?;
+
+        if self.include_deps {
+            for dependency_path in self.local_dependency_paths()? {
+                self.format_package(&dependency_path, &handler, &mut unformatted_files)?;
             }
-            fs::remove_dir_all(local_build_directory.to_str().unwrap()).expect("Failed to remove build directory");
-            fs::remove_dir_all(local_outputs_directory.to_str().unwrap()).expect("Failed to remove outputs directory");
-            for file_path in local_source_files.clone() {
-                let code = fs::read_to_string(file_path.to_str().unwrap()).expect("Failed to read file");
-                let normalized_code = normalize_code(&code);
-                fs::write(file_path.to_str().unwrap(), normalized_code).expect("Failed to write file");
+        }
+
+        if self.mode != FormatMode::Write && !unformatted_files.is_empty() {
+            for file_path in &unformatted_files {
+                println!("{}", file_path.display());
             }
+            return Err(CliError::build_error(UnformattedFilesError { count: unformatted_files.len() }).into());
         }
+
         Ok(())
     }
 
-}
+    /// Resolves the local path dependencies to also format. Offline, this reads them straight
+    /// out of `leo.toml` with no network access; otherwise it goes through the `Retriever`,
+    /// which may also fetch published dependencies over the network.
+    fn local_dependency_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        if self.offline {
+            let manifest = Manifest::read_from_dir(&self.package_path).map_err(CliError::build_error)?;
+            let paths = manifest
+                .dependencies()
+                .iter()
+                .flatten()
+                .filter_map(|dependency| dependency.path().map(|path| self.package_path.join(path)))
+                .collect();
+            return Ok(paths);
+        }
 
-#[allow(clippy::too_many_arguments)]
-fn compile_leo_file<N: Network>(
-    file_path: PathBuf,
-    program_id: &ProgramID<N>,
-    outputs: &Path,
-    build: &Path,
-    handler: &Handler,
-    stubs: IndexMap<Symbol, Stub>,
-)  -> Result<(), Error> {
-    let program_name = program_id.name().to_string();
-    let mut compiler = Compiler::<N>::new(
-        program_name.clone(),
-        program_id.network().to_string(),
-        handler,
-        file_path.clone(),
-        outputs.to_path_buf(),
-        Some(CompilerOptions::default()),
-        stubs,
-    );
-    compiler.compile()?;
-    Ok(())
-}
-fn normalize_code(code: &str) -> String {
-    let mut result = String::new();
-    let mut indent_level = 0;
-    let mut inside_brace = false;
-    let mut inside_comment = false;
-
-    let mut chars = code.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        match c {
-            '{' => {
-                
-                result.push(c);
-                result.push('\n');
-                indent_level += 1;
-                add_indentation(&mut result, indent_level);
-                inside_brace = true;
-            }
-            '}' => {
-                if inside_brace {
-                    indent_level -= 1;
-                    result.push('\n');
-                    add_indentation(&mut result, indent_level);
-                    result.push(c);
-                    result.push('\n');
-                    add_indentation(&mut result, indent_level);
-                    inside_brace = indent_level > 0;
-                }
-            }
-            ';' => {
-                result.push(c);
-                result.push('\n');
-                add_indentation(&mut result, indent_level);
-                inside_comment = false;
-            }
-            ':' => {
-                result.push(c);
-                result.push(' '); // Add space after colon for readability
-            }
-            '(' => {
-                result.push(c);
-                result.push(' ');
-            }
-            ')' => {
-                result.push(' ');
-                result.push(c);
-            }
-            '/' => {
-                if chars.peek() == Some(&'/') {
-                    inside_comment = true;
-                    result.push(c);
-                    result.push(chars.next().unwrap()); // Skip the next '/'
-                    
-                } else {
-                    result.push(c);
-                }
-            }
-            '\n' => {
-                if inside_comment {
-                    inside_comment = false;
-                    result.push('\n');
-                    add_indentation(&mut result, indent_level);
-                }
-                // Ignore explicit newlines in the input
+        let main_sym = Symbol::intern(&self.program_id.name().to_string());
+        let mut retriever = Retriever::<N>::new(main_sym, &self.package_path, &self.home_path, self.endpoint.clone())
+            .map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
+        let local_dependencies =
+            retriever.retrieve().map_err(|err| UtilError::failed_to_retrieve_dependencies(err, Default::default()))?;
+        local_dependencies
+            .into_iter()
+            .map(|dependency| retriever.prepare_local(dependency).map(|(path, _stubs)| path).map_err(CliError::build_error))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Formats every source file in `package_path`'s `SourceDirectory`, honoring `self.mode`.
+    fn format_package(&self, package_path: &Path, handler: &Handler, unformatted_files: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let source_files = SourceDirectory::files(package_path).map_err(CliError::build_error)?;
+        SourceDirectory::check_files(&source_files).map_err(CliError::build_error)?;
+
+        for file_path in source_files {
+            let code = fs::read_to_string(&file_path).map_err(CliError::build_error)?;
+            let normalized_code = format_source::<N>(&code, handler, &self.config)?;
+            if normalized_code == code {
                 continue;
             }
-            ' ' => {
-                // Skip multiple spaces
-                if !result.ends_with(' ') {
-                    result.push(c);
+            match self.mode {
+                FormatMode::Write => {
+                    fs::write(&file_path, normalized_code).map_err(CliError::build_error)?;
                 }
-            }
-            _ => {
-                if inside_comment {
-                    if c == '\n' {
-                        inside_comment = false;
-                    }
+                FormatMode::Check => {
+                    unformatted_files.push(file_path);
+                }
+                FormatMode::Diff => {
+                    println!("{}", diff::unified_diff(&file_path.display().to_string(), &code, &normalized_code));
+                    unformatted_files.push(file_path);
                 }
-                result.push(c);
             }
         }
+        Ok(())
+    }
+}
+/// Formats `code` by parsing it into a Leo AST and re-emitting canonical source text from a
+/// dedicated pretty-printer, rather than rewriting characters in place. This is what keeps the
+/// formatter from corrupting comments, string/char literals, `::` path separators, `->`, and
+/// comparison operators the way the old character-scanning pass did, and it is idempotent:
+/// formatting already-formatted output returns it unchanged.
+fn format_source<N: Network>(code: &str, handler: &Handler, config: &FormatterConfig) -> Result<String, Error> {
+    let node_builder = NodeBuilder::default();
+    let ast = leo_parser::parse_ast::<N>(handler, &node_builder, code, Default::default())
+        .map_err(CliError::build_error)?;
+    let comments = CommentMap::collect(code);
+    Ok(PrettyPrinter::new(&comments, config).print(&ast))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::TestnetV0;
+
+    fn format(code: &str) -> String {
+        let handler = Handler::default();
+        let config = FormatterConfig::default();
+        format_source::<TestnetV0>(code, &handler, &config).expect("formatting should succeed")
+    }
+
+    #[test]
+    fn formatting_is_idempotent_with_a_trailing_comment() {
+        let source = r#"
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        let x: u8 = a; // keep this note
+        return x;
+    }
+}
+"#;
+        let once = format(source);
+        let twice = format_source::<TestnetV0>(&once, &Handler::default(), &FormatterConfig::default())
+            .expect("re-formatting already-formatted output should succeed");
+
+        assert_eq!(once, twice, "formatting an already-formatted program must be a no-op");
+        assert_eq!(once.matches("keep this note").count(), 1, "the trailing comment must not be duplicated");
+    }
+
+    #[test]
+    fn collapse_simple_blocks_does_not_swallow_a_commented_statement() {
+        let source = r#"
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        // keep this note
+        return a;
+    }
+}
+"#;
+        let config = FormatterConfig { collapse_simple_blocks: true, ..FormatterConfig::default() };
+        let formatted = format_source::<TestnetV0>(source, &Handler::default(), &config)
+            .expect("formatting should succeed");
+
+        assert!(formatted.contains("return a;"), "the commented statement must not be swallowed into the comment");
+        assert!(formatted.contains("// keep this note"), "the leading comment must still be emitted");
+    }
+
+    #[test]
+    fn imports_are_preserved() {
+        let source = r#"
+import foo.aleo;
+
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        return a;
+    }
+}
+"#;
+        let formatted = format(source);
+        assert!(formatted.contains("import foo.aleo;"), "formatting must not drop import declarations");
     }
 
-    // Remove any trailing newlines or spaces
-    result.trim_end().to_string()
+    #[test]
+    fn comment_before_a_closing_brace_is_preserved() {
+        let source = r#"
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        return a;
+        // trailing note before the closing brace
+    }
+}
+"#;
+        let formatted = format(source);
+        assert!(
+            formatted.contains("// trailing note before the closing brace"),
+            "a comment sitting just above a block's closing brace must not be dropped"
+        );
+    }
+
+    #[test]
+    fn comment_inside_a_struct_body_is_preserved() {
+        let source = r#"
+program test.aleo {
+    struct Point {
+        // the x coordinate
+        x: u8,
+        y: u8,
+    }
+
+    transition main(a: u8) -> u8 {
+        return a;
+    }
 }
-fn add_indentation(result: &mut String, indent_level: usize) {
-    for _ in 0..indent_level {
-        result.push_str("    "); // 4 spaces for indentation
+"#;
+        let formatted = format(source);
+        assert!(
+            formatted.contains("// the x coordinate"),
+            "a comment inside a struct body must not be dropped"
+        );
     }
 }
@@ -0,0 +1,334 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A pretty-printer that emits canonical Leo source text from a parsed [`Ast`], rather than
+//! reformatting raw characters. Because it works on the parsed representation it never
+//! mangles comments, string/char literals, `::` path separators, `->`, or comparison
+//! operators the way a character-scanning formatter would, and running it twice on its own
+//! output is a no-op.
+
+use leo_ast::{Ast, ConstDeclaration, Function, Mapping, Program, ProgramScope, Statement, Struct};
+use leo_span::Symbol;
+
+use crate::linter::comments::CommentMap;
+use crate::linter::config::FormatterConfig;
+
+/// Walks a parsed program and writes canonical Leo source text, interleaving comments
+/// collected from the original source alongside the node they were written next to. Layout
+/// choices (indentation, line width, blank lines between items, ...) come from a
+/// [`FormatterConfig`] rather than being hardcoded.
+pub struct PrettyPrinter<'a> {
+    comments: &'a CommentMap,
+    config: &'a FormatterConfig,
+    indent: usize,
+    out: String,
+}
+
+impl<'a> PrettyPrinter<'a> {
+    pub fn new(comments: &'a CommentMap, config: &'a FormatterConfig) -> Self {
+        Self { comments, config, indent: 0, out: String::new() }
+    }
+
+    /// Formats `ast` into canonical Leo source text.
+    pub fn print(mut self, ast: &Ast) -> String {
+        self.print_program_scopes(ast);
+        self.out.trim_end().to_string() + "\n"
+    }
+
+    fn print_program_scopes(&mut self, ast: &Ast) {
+        let program = ast.as_repr();
+        let wrote_imports = self.print_imports(program);
+
+        let mut scopes: Vec<(&Symbol, &ProgramScope)> = program.program_scopes.iter().collect();
+        scopes.sort_by_key(|(_, scope)| scope.span.lo);
+
+        for (i, (_, scope)) in scopes.iter().enumerate() {
+            if i > 0 || wrote_imports {
+                self.blank_lines_between_items();
+            }
+            self.print_program_scope(scope);
+        }
+    }
+
+    /// Re-emits every `import foo.aleo;` declaration so formatting never silently drops a
+    /// program's imports. Returns whether anything was written.
+    fn print_imports(&mut self, program: &Program) -> bool {
+        if program.imports.is_empty() {
+            return false;
+        }
+        for name in program.imports.keys() {
+            self.push_line(&format!("import {name}.aleo;"));
+        }
+        true
+    }
+
+    fn print_program_scope(&mut self, scope: &ProgramScope) {
+        self.push_leading_comments(scope.span.line_start as usize);
+        self.push_line(&format!("program {} {{", scope.program_id));
+        self.indent += 1;
+
+        let mut wrote_decl = false;
+        for (_, const_decl) in scope.consts.iter() {
+            self.print_const(const_decl);
+            wrote_decl = true;
+        }
+        for (_, struct_) in scope.structs.iter() {
+            if wrote_decl {
+                self.blank_lines_between_items();
+            }
+            self.print_struct(struct_);
+            wrote_decl = true;
+        }
+        for (_, mapping) in scope.mappings.iter() {
+            if wrote_decl {
+                self.blank_lines_between_items();
+            }
+            self.print_mapping(mapping);
+            wrote_decl = true;
+        }
+        for (_, function) in scope.functions.iter() {
+            if wrote_decl {
+                self.blank_lines_between_items();
+            }
+            self.print_function(function);
+            wrote_decl = true;
+        }
+
+        self.indent -= 1;
+        self.push_leading_comments(scope.span.line_stop as usize);
+        self.push_line("}");
+    }
+
+    fn print_const(&mut self, const_decl: &ConstDeclaration) {
+        self.push_leading_comments(const_decl.span.line_start as usize);
+        self.push_line_with_trailing(
+            &format!("const {}: {} = {};", const_decl.place, const_decl.type_, const_decl.value),
+            const_decl.span.line_stop as usize,
+        );
+    }
+
+    fn print_struct(&mut self, struct_: &Struct) {
+        self.push_leading_comments(struct_.span.line_start as usize);
+        let keyword = if struct_.is_record { "record" } else { "struct" };
+        self.push_line(&format!("{} {} {{", keyword, struct_.identifier));
+        self.indent += 1;
+        for member in struct_.members.iter() {
+            self.push_leading_comments(member.span.line_start as usize);
+            self.push_line_with_trailing(
+                &format!("{}: {},", member.identifier, member.type_),
+                member.span.line_stop as usize,
+            );
+        }
+        self.indent -= 1;
+        self.push_leading_comments(struct_.span.line_stop as usize);
+        self.push_line("}");
+    }
+
+    fn print_mapping(&mut self, mapping: &Mapping) {
+        self.push_leading_comments(mapping.span.line_start as usize);
+        self.push_line_with_trailing(
+            &format!("mapping {}: {} => {};", mapping.identifier, mapping.key_type, mapping.value_type),
+            mapping.span.line_stop as usize,
+        );
+    }
+
+    fn print_function(&mut self, function: &Function) {
+        self.push_leading_comments(function.span.line_start as usize);
+        let keyword = if function.is_transition() { "transition" } else { "function" };
+        let params: Vec<String> =
+            function.input.iter().map(|input| format!("{}: {}", input.identifier(), input.type_())).collect();
+        let outputs = function
+            .output
+            .iter()
+            .map(|output| output.type_().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let output_suffix = if function.output.len() == 1 {
+            format!(" -> {outputs}")
+        } else if function.output.is_empty() {
+            String::new()
+        } else {
+            format!(" -> ({outputs})")
+        };
+
+        let one_line_signature =
+            format!("{keyword} {}({}){output_suffix} {{", function.identifier, params.join(", "));
+        if params.len() > 1 && self.current_indent_width() + one_line_signature.len() > self.config.max_width {
+            self.push_line(&format!("{keyword} {}(", function.identifier));
+            self.indent += 1;
+            for param in &params {
+                self.push_line(&format!("{param},"));
+            }
+            self.indent -= 1;
+            self.push_line(&format!("){output_suffix} {{"));
+        } else {
+            self.push_line(&one_line_signature);
+        }
+
+        self.indent += 1;
+        self.print_block_statements(&function.block.statements);
+        self.indent -= 1;
+        self.push_leading_comments(function.block.span.line_stop as usize);
+        self.push_line("}");
+    }
+
+    /// Prints a block's statements, collapsing it onto the header line when the block holds a
+    /// single simple statement and `collapse_simple_blocks` is enabled. Never collapses a
+    /// statement that carries a leading or trailing `//` comment: inlining it would swallow
+    /// everything after the `//` into that comment, silently commenting out code.
+    fn print_block_statements(&mut self, statements: &[Statement]) {
+        if self.config.collapse_simple_blocks {
+            if let [single] = statements {
+                let span = single.span();
+                let has_comment =
+                    self.comments.has_leading(span.line_start as usize) || self.comments.has_trailing(span.line_stop as usize);
+                if !has_comment
+                    && !matches!(single, Statement::Block(_) | Statement::Conditional(_) | Statement::Iteration(_))
+                {
+                    let last = self.out.len() - 1;
+                    self.out.truncate(last); // drop the header's trailing newline
+                    self.out.push(' ');
+                    self.print_statement_inline(single);
+                    self.out.push(' ');
+                    return;
+                }
+            }
+        }
+        for statement in statements {
+            self.print_statement(statement);
+        }
+    }
+
+    fn print_statement(&mut self, statement: &Statement) {
+        let span = statement.span();
+        self.push_leading_comments(span.line_start as usize);
+
+        match statement {
+            Statement::Block(block) => {
+                self.push_line("{");
+                self.indent += 1;
+                self.print_block_statements(&block.statements);
+                self.indent -= 1;
+                self.push_leading_comments(block.span.line_stop as usize);
+                self.push_line("}");
+            }
+            Statement::Conditional(conditional) => {
+                self.push_line(&format!("if {} {{", conditional.condition));
+                self.indent += 1;
+                self.print_block_statements(&conditional.then.statements);
+                self.indent -= 1;
+                if let Some(otherwise) = &conditional.otherwise {
+                    self.push_leading_comments(conditional.then.span.line_stop as usize);
+                    self.push_line("} else {");
+                    self.indent += 1;
+                    match otherwise.as_ref() {
+                        // The `else` braces are already printed; don't wrap the block in another pair.
+                        Statement::Block(block) => self.print_block_statements(&block.statements),
+                        other => self.print_statement(other),
+                    }
+                    self.indent -= 1;
+                    self.push_leading_comments(span.line_stop as usize);
+                } else {
+                    self.push_leading_comments(conditional.then.span.line_stop as usize);
+                }
+                self.push_line("}");
+            }
+            Statement::Iteration(iteration) => {
+                self.push_line(&format!(
+                    "for {}: {} in {}..{} {{",
+                    iteration.variable, iteration.type_, iteration.start, iteration.stop
+                ));
+                self.indent += 1;
+                self.print_block_statements(&iteration.block.statements);
+                self.indent -= 1;
+                self.push_leading_comments(iteration.block.span.line_stop as usize);
+                self.push_line("}");
+            }
+            Statement::Return(return_stmt) => {
+                self.push_line_with_trailing(&format!("return {};", return_stmt.expression), span.line_stop as usize);
+            }
+            Statement::Definition(definition) => {
+                let keyword = if definition.declaration_type.is_const() { "const" } else { "let" };
+                self.push_line_with_trailing(
+                    &format!("{} {}: {} = {};", keyword, definition.place, definition.type_, definition.value),
+                    span.line_stop as usize,
+                );
+            }
+            Statement::Assign(assign) => {
+                self.push_line_with_trailing(
+                    &format!("{} = {};", assign.place, assign.value),
+                    span.line_stop as usize,
+                );
+            }
+            Statement::Assert(assert) => {
+                self.push_line_with_trailing(&format!("{};", assert), span.line_stop as usize);
+            }
+            Statement::Expression(expression) => {
+                self.push_line_with_trailing(&format!("{};", expression.expression), span.line_stop as usize);
+            }
+            Statement::Console(console) => {
+                self.push_line_with_trailing(&format!("{};", console), span.line_stop as usize);
+            }
+        }
+    }
+
+    /// Prints `statement` without its leading indentation, newline, or attached comments, so it
+    /// can be inlined onto a collapsed block's header line.
+    fn print_statement_inline(&mut self, statement: &Statement) {
+        let start = self.out.len();
+        self.print_statement(statement);
+        let printed = self.out.split_off(start);
+        self.out.push_str(printed.trim());
+    }
+
+    fn push_leading_comments(&mut self, line: usize) {
+        for comment in self.comments.leading(line) {
+            self.push_line(&comment.text);
+        }
+    }
+
+    fn push_line(&mut self, text: &str) {
+        // After a collapsed block the current line is still open; continue it without indenting.
+        if self.out.is_empty() || self.out.ends_with('\n') {
+            self.out.push_str(&self.config.indent_unit().repeat(self.indent));
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn push_line_with_trailing(&mut self, text: &str, line: usize) {
+        let trailing = self.comments.trailing(line).map(|c| c.text.clone());
+        self.out.push_str(&self.config.indent_unit().repeat(self.indent));
+        self.out.push_str(text);
+        if let Some(trailing) = trailing {
+            self.out.push(' ');
+            self.out.push_str(&trailing);
+        }
+        self.out.push('\n');
+    }
+
+    fn blank_lines_between_items(&mut self) {
+        for _ in 0..self.config.blank_lines_between_items {
+            self.out.push('\n');
+        }
+    }
+
+    /// Estimated width in columns of the current indentation, used to decide whether a signature
+    /// needs wrapping.
+    fn current_indent_width(&self) -> usize {
+        self.indent * self.config.indent_width
+    }
+}